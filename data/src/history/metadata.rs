@@ -1,14 +1,112 @@
+use std::collections::HashMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
 use chrono::{format::SecondsFormat, DateTime, Utc};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use tokio::fs;
+use tokio::sync::{Mutex, OnceCell, RwLock};
 
 use crate::history::{dir_path, Error, Kind};
 use crate::{message, server, Message};
 
+/// Length, in bytes, of the random XNonce prefixed to each encrypted file.
+const NONCE_LEN: usize = 24;
+/// Length, in bytes, of the salt used to derive an [`EncryptionKey`] from a passphrase.
+const SALT_LEN: usize = 16;
+
+/// A 32-byte XChaCha20 key derived from a user passphrase.
+///
+/// Metadata files are only encrypted when a [`FilesystemStore`] is configured with one;
+/// stores left with `encryption: None` keep reading and writing plaintext JSON, which is
+/// how the encrypted and unencrypted formats coexist in the same data dir.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Derives a key from `passphrase` using Argon2id and the data dir's persistent salt.
+    pub async fn derive(passphrase: &str) -> Result<Self, Error> {
+        let salt = salt().await?;
+
+        let mut key = [0; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|_| Error::Encryption)?;
+
+        Ok(Self(key))
+    }
+}
+
+/// Process-wide cache of the data dir's salt, so concurrent [`EncryptionKey::derive`]
+/// calls can't each read a missing salt file and generate (and persist) their own.
+static SALT: OnceCell<[u8; SALT_LEN]> = OnceCell::const_new();
+
+/// Loads the data dir's salt, generating and persisting one on first use.
+async fn salt() -> Result<[u8; SALT_LEN], Error> {
+    SALT.get_or_try_init(|| async {
+        let path = dir_path().await?.join("salt");
+
+        if let Ok(bytes) = fs::read(&path).await {
+            if let Ok(salt) = bytes.try_into() {
+                return Ok(salt);
+            }
+        }
+
+        let mut salt = [0; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+
+        // Write via a sibling temp file + rename, same as `write_atomic`, so a crash
+        // mid-write can't truncate the salt file and silently regenerate a different
+        // salt (and by extension a different key) on next boot.
+        let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
+        fs::write(&tmp_path, salt).await?;
+        fs::rename(&tmp_path, &path).await?;
+
+        Ok(salt)
+    })
+    .await
+    .map(|salt| *salt)
+}
+
+/// Encrypts `bytes` with XChaCha20-Poly1305, framed as `nonce || ciphertext || tag`.
+///
+/// Using an AEAD rather than a bare stream cipher means a flipped ciphertext bit fails
+/// the Poly1305 tag check in [`decrypt`] instead of silently flipping a plaintext bit
+/// that might still happen to parse as valid `Metadata` JSON.
+fn encrypt(key: &EncryptionKey, bytes: &[u8]) -> Vec<u8> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext = XChaCha20Poly1305::new(&key.0.into())
+        .encrypt(&nonce, bytes)
+        .expect("encrypting metadata should never fail");
+
+    let mut framed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    framed.extend(nonce);
+    framed.extend(ciphertext);
+    framed
+}
+
+/// Decrypts `bytes` previously produced by [`encrypt`]. Returns `None` if the data is
+/// too short, was encrypted with a different key, or fails Poly1305 tag verification —
+/// callers treat that the same as a missing file.
+fn decrypt(key: &EncryptionKey, bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < NONCE_LEN {
+        return None;
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+
+    XChaCha20Poly1305::new(&key.0.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .ok()
+}
+
 #[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
 pub struct Metadata {
     pub read_marker: Option<ReadMarker>,
@@ -57,71 +155,576 @@ pub fn latest_triggers_unread(messages: &[Message]) -> Option<DateTime<Utc>> {
         .map(|message| message.server_time)
 }
 
-pub async fn load(server: server::Server, kind: Kind) -> Result<Metadata, Error> {
-    let path = path(&server, &kind).await?;
+/// Applies `read_marker` to `existing` if it's newer, returning `None` when `existing`
+/// already has an equal or newer marker and nothing needs to be written.
+///
+/// Pulled out as a pure function so every `MetadataStore::update` override — which must
+/// each hold their own lock across the read-check-write to stay race-free — shares the
+/// exact same monotonicity rule.
+fn merged_read_marker(existing: Metadata, read_marker: ReadMarker) -> Option<Metadata> {
+    if existing
+        .read_marker
+        .is_some_and(|existing_marker| existing_marker >= read_marker)
+    {
+        return None;
+    }
+
+    Some(Metadata {
+        read_marker: Some(read_marker),
+        last_triggers_unread: existing.last_triggers_unread,
+    })
+}
+
+/// Where read-state metadata is read from and written to.
+///
+/// The on-disk [`FilesystemStore`] is the default, but this is behind a trait so
+/// sandboxed or multi-instance setups can swap in something that never touches disk
+/// (see [`InMemoryStore`]) without changing any call site.
+#[async_trait::async_trait]
+pub trait MetadataStore: Send + Sync {
+    async fn get(&self, server: &server::Server, kind: &Kind) -> Result<Option<Metadata>, Error>;
+
+    async fn put(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        metadata: Metadata,
+    ) -> Result<(), Error>;
+
+    /// Default implementation for backends with no cheaper way to serialize a
+    /// read-modify-write; it does a plain `get` then `put`, so backends that can race
+    /// (anything backed by shared mutable state, like [`FilesystemStore`] and
+    /// [`InMemoryStore`]) must override this with one that holds a lock across both.
+    async fn update(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        read_marker: &ReadMarker,
+    ) -> Result<(), Error> {
+        let metadata = self.get(server, kind).await?.unwrap_or_default();
+
+        match merged_read_marker(metadata, *read_marker) {
+            Some(metadata) => self.put(server, kind, metadata).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Stores metadata as a JSON file per `(server, kind)`, named after a seahash of the key.
+///
+/// This is the original, and still default, backend. Set `encryption` to keep files
+/// encrypted at rest; leave it `None` to keep reading and writing plaintext JSON.
+///
+/// Writes go to a sibling temp file followed by a rename, so a crash mid-write never
+/// leaves a truncated JSON file behind. Every operation for a given path takes that
+/// path's [`RwLock`]: `put`/`update` take it exclusively, so a read-modify-write can't
+/// clobber a newer read marker written in between, while `get` takes it in shared mode
+/// for its whole read-and-cache critical section, so a write can never land — and evict
+/// the read cache below — while a `get` is still holding a (possibly stale) cached
+/// result for the same path. Concurrent `get`s sharing the lock still share a single
+/// disk read via `in_flight_reads` rather than each issuing their own.
+#[derive(Default)]
+pub struct FilesystemStore {
+    pub encryption: Option<EncryptionKey>,
+    locks: Mutex<HashMap<PathBuf, Arc<RwLock<()>>>>,
+    in_flight_reads: Mutex<HashMap<PathBuf, Arc<OnceCell<Option<Metadata>>>>>,
+}
+
+impl FilesystemStore {
+    pub fn new(encryption: Option<EncryptionKey>) -> Self {
+        Self {
+            encryption,
+            ..Self::default()
+        }
+    }
+
+    async fn path(&self, server: &server::Server, kind: &Kind) -> Result<PathBuf, Error> {
+        let dir = dir_path().await?;
+
+        let name = match kind {
+            Kind::Server => format!("{server}-metadata"),
+            Kind::Channel(channel) => format!("{server}channel{channel}-metadata"),
+            Kind::Query(nick) => format!("{server}nickname{}-metadata", nick),
+            Kind::Logs => "log-metadata".to_string(),
+        };
 
-    if let Ok(bytes) = fs::read(path).await {
-        Ok(serde_json::from_slice(&bytes).unwrap_or_default())
-    } else {
-        Ok(Metadata::default())
+        let hashed_name = seahash::hash(name.as_bytes());
+
+        Ok(dir.join(format!("{hashed_name}.json")))
+    }
+
+    async fn lock_for(&self, path: &Path) -> Arc<RwLock<()>> {
+        let mut locks = self.locks.lock().await;
+
+        // Drop entries nobody else is holding a guard for, so this map stays sized to
+        // paths with an operation currently in flight rather than growing unboundedly
+        // over the store's lifetime.
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+        locks
+            .entry(path.to_path_buf())
+            .or_insert_with(|| Arc::new(RwLock::new(())))
+            .clone()
+    }
+
+    /// Serializes writes to `path` against both other writes and in-flight reads.
+    async fn write_lock(&self, path: &Path) -> tokio::sync::OwnedRwLockWriteGuard<()> {
+        self.lock_for(path).await.write_owned().await
+    }
+
+    /// Lets concurrent reads of `path` proceed together, but blocks until any write
+    /// in flight for it has fully landed (and evicted the read cache) first.
+    async fn read_lock(&self, path: &Path) -> tokio::sync::OwnedRwLockReadGuard<()> {
+        self.lock_for(path).await.read_owned().await
+    }
+
+    async fn read_from_disk(&self, path: &Path) -> Result<Option<Metadata>, Error> {
+        let Ok(bytes) = fs::read(path).await else {
+            return Ok(None);
+        };
+
+        let Some(bytes) = (match &self.encryption {
+            Some(key) => decrypt(key, &bytes),
+            None => Some(bytes),
+        }) else {
+            return Ok(None);
+        };
+
+        Ok(Some(serde_json::from_slice(&bytes).unwrap_or_default()))
+    }
+
+    /// Writes `metadata` to `path` via a temp file + rename so readers never observe
+    /// a partially-written file.
+    async fn write_atomic(&self, path: &Path, metadata: &Metadata) -> Result<(), Error> {
+        let bytes = serde_json::to_vec(metadata)?;
+
+        let bytes = match &self.encryption {
+            Some(key) => encrypt(key, &bytes),
+            None => bytes,
+        };
+
+        let tmp_path = PathBuf::from(format!("{}.tmp", path.display()));
+
+        fs::write(&tmp_path, &bytes).await?;
+        fs::rename(&tmp_path, path).await?;
+
+        Ok(())
     }
 }
 
-pub async fn save(
-    server: &server::Server,
-    kind: &Kind,
-    messages: &[Message],
-    read_marker: Option<ReadMarker>,
-) -> Result<(), Error> {
-    let bytes = serde_json::to_vec(&Metadata {
-        read_marker,
-        last_triggers_unread: latest_triggers_unread(messages),
-    })?;
+#[async_trait::async_trait]
+impl MetadataStore for FilesystemStore {
+    async fn get(&self, server: &server::Server, kind: &Kind) -> Result<Option<Metadata>, Error> {
+        let path = self.path(server, kind).await?;
+
+        // Held for the whole read-and-cache critical section below, so a `put`/`update`
+        // for this path can't land (and evict the cache) while we're still holding a
+        // stale cached result — it'll block on `write_lock` until we're done and have
+        // released the map entry.
+        let _guard = self.read_lock(&path).await;
 
-    let path = path(server, kind).await?;
+        // Concurrent callers for a path with no load already in flight share the first
+        // loader's disk read instead of each issuing their own.
+        let cell = self
+            .in_flight_reads
+            .lock()
+            .await
+            .entry(path.clone())
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
 
-    fs::write(path, &bytes).await?;
+        let result = cell
+            .get_or_try_init(|| self.read_from_disk(&path))
+            .await
+            .map(|metadata| *metadata);
 
-    Ok(())
+        self.in_flight_reads.lock().await.remove(&path);
+
+        result
+    }
+
+    async fn put(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        metadata: Metadata,
+    ) -> Result<(), Error> {
+        let path = self.path(server, kind).await?;
+        let _guard = self.write_lock(&path).await;
+
+        let result = self.write_atomic(&path, &metadata).await;
+
+        // No `get` can be holding a cached result for this path right now — the write
+        // lock above waits out every reader first — so this just clears whatever a
+        // reader before us left behind, guaranteeing the next `get` re-reads the file
+        // we just wrote rather than reusing a now-stale cache entry.
+        self.in_flight_reads.lock().await.remove(&path);
+
+        result
+    }
+
+    async fn update(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        read_marker: &ReadMarker,
+    ) -> Result<(), Error> {
+        let path = self.path(server, kind).await?;
+        let _guard = self.write_lock(&path).await;
+
+        let metadata = self.read_from_disk(&path).await?.unwrap_or_default();
+
+        let result = match merged_read_marker(metadata, *read_marker) {
+            Some(metadata) => self.write_atomic(&path, &metadata).await,
+            None => Ok(()),
+        };
+
+        self.in_flight_reads.lock().await.remove(&path);
+
+        result
+    }
 }
 
-pub async fn update(
-    server: &server::Server,
-    kind: &Kind,
-    read_marker: &ReadMarker,
-) -> Result<(), Error> {
-    let metadata = load(server.clone(), kind.clone()).await?;
+/// A cached [`Metadata`] entry, optionally expiring after [`InMemoryStore`]'s configured TTL.
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    metadata: Metadata,
+    expires_at: Option<DateTime<Utc>>,
+}
 
-    if metadata
-        .read_marker
-        .is_some_and(|metadata_read_marker| metadata_read_marker >= *read_marker)
-    {
-        return Ok(());
+impl CacheEntry {
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+/// Keeps metadata in memory only, for ephemeral or private sessions where nothing
+/// should touch disk. If `ttl` is set, an entry older than it reads as absent and is
+/// removed by the next `get` of its key; expired entries nobody reads stay in memory.
+#[derive(Default)]
+pub struct InMemoryStore {
+    ttl: Option<chrono::Duration>,
+    entries: tokio::sync::RwLock<std::collections::HashMap<(server::Server, Kind), CacheEntry>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ttl(ttl: chrono::Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            entries: Default::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataStore for InMemoryStore {
+    async fn get(&self, server: &server::Server, kind: &Kind) -> Result<Option<Metadata>, Error> {
+        let key = (server.clone(), kind.clone());
+
+        let mut entries = self.entries.write().await;
+
+        match entries.get(&key) {
+            Some(entry) if entry.is_expired() => {
+                entries.remove(&key);
+                Ok(None)
+            }
+            Some(entry) => Ok(Some(entry.metadata)),
+            None => Ok(None),
+        }
+    }
+
+    async fn put(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        metadata: Metadata,
+    ) -> Result<(), Error> {
+        let expires_at = self.ttl.map(|ttl| Utc::now() + ttl);
+
+        self.entries.write().await.insert(
+            (server.clone(), kind.clone()),
+            CacheEntry {
+                metadata,
+                expires_at,
+            },
+        );
+
+        Ok(())
     }
 
-    let bytes = serde_json::to_vec(&Metadata {
-        read_marker: Some(*read_marker),
-        last_triggers_unread: metadata.last_triggers_unread,
-    })?;
+    async fn update(
+        &self,
+        server: &server::Server,
+        kind: &Kind,
+        read_marker: &ReadMarker,
+    ) -> Result<(), Error> {
+        let key = (server.clone(), kind.clone());
+
+        // Hold the write lock across the whole read-check-write so two concurrent
+        // `update` calls for the same key can't both read the same stale entry and
+        // have the later `insert` clobber a newer read marker.
+        let mut entries = self.entries.write().await;
 
-    let path = path(server, kind).await?;
+        let metadata = match entries.get(&key) {
+            Some(entry) if !entry.is_expired() => entry.metadata,
+            _ => Metadata::default(),
+        };
 
-    fs::write(path, &bytes).await?;
+        let Some(metadata) = merged_read_marker(metadata, *read_marker) else {
+            return Ok(());
+        };
 
-    Ok(())
+        let expires_at = self.ttl.map(|ttl| Utc::now() + ttl);
+        entries.insert(
+            key,
+            CacheEntry {
+                metadata,
+                expires_at,
+            },
+        );
+
+        Ok(())
+    }
 }
 
-async fn path(server: &server::Server, kind: &Kind) -> Result<PathBuf, Error> {
-    let dir = dir_path().await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> EncryptionKey {
+        EncryptionKey([seed; 32])
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trips() {
+        let key = key(1);
+        let plaintext = br#"{"read_marker":"2026-07-29T10:00:00.000Z"}"#;
+
+        let ciphertext = encrypt(&key, plaintext);
+        let decrypted = decrypt(&key, &ciphertext).expect("decryption should succeed");
+
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn decrypt_rejects_tampered_ciphertext() {
+        let key = key(2);
+        let mut ciphertext = encrypt(&key, br#"{"read_marker":"2026-07-29T10:00:00.000Z"}"#);
+
+        // Flip a bit inside the ciphertext (past the leading nonce, on the byte that
+        // encrypts the leading '2' of "2026") the way corruption or tampering on disk
+        // would; a bare stream cipher would happily "decrypt" this into a year of 3026,
+        // still-parseable JSON, which is exactly what the AEAD tag check here catches.
+        let year_digit = NONCE_LEN + 16;
+        ciphertext[year_digit] ^= 0x01;
+
+        assert!(decrypt(&key, &ciphertext).is_none());
+    }
+
+    #[test]
+    fn decrypt_rejects_wrong_key() {
+        let ciphertext = encrypt(&key(3), b"some metadata");
+
+        assert!(decrypt(&key(4), &ciphertext).is_none());
+    }
+
+    #[test]
+    fn decrypt_rejects_input_shorter_than_the_nonce() {
+        assert!(decrypt(&key(5), &[0; NONCE_LEN - 1]).is_none());
+    }
+
+    fn unique_temp_path(label: &str) -> PathBuf {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "halloy-metadata-test-{label}-{}-{n}.json",
+            std::process::id()
+        ))
+    }
+
+    fn marker(seconds: u32) -> ReadMarker {
+        format!("2026-01-01T00:00:{seconds:02}.000Z")
+            .parse()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_lock_serializes_same_path() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Arc::new(FilesystemStore::new(None));
+        let path = unique_temp_path("write-lock");
+        let concurrent_holders = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..8).map(|_| {
+            let store = store.clone();
+            let path = path.clone();
+            let concurrent_holders = concurrent_holders.clone();
+
+            tokio::spawn(async move {
+                let _guard = store.write_lock(&path).await;
+
+                let holders = concurrent_holders.fetch_add(1, Ordering::SeqCst) + 1;
+                assert_eq!(holders, 1, "write_lock let two holders in at once");
 
-    let name = match kind {
-        Kind::Server => format!("{server}-metadata"),
-        Kind::Channel(channel) => format!("{server}channel{channel}-metadata"),
-        Kind::Query(nick) => format!("{server}nickname{}-metadata", nick),
-        Kind::Logs => "log-metadata".to_string(),
-    };
+                tokio::task::yield_now().await;
 
-    let hashed_name = seahash::hash(name.as_bytes());
+                concurrent_holders.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
 
-    Ok(dir.join(format!("{hashed_name}.json")))
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn read_lock_and_write_lock_exclude_each_other_for_same_path() {
+        use std::sync::atomic::{AtomicIsize, Ordering};
+
+        let store = Arc::new(FilesystemStore::new(None));
+        let path = unique_temp_path("read-write-exclusion");
+
+        // Goes positive while any number of readers hold the lock, and must hit
+        // exactly -1 while the writer holds it; a `get` observing a stale cache after
+        // a concurrent `put` has landed is exactly the kind of overlap this guards
+        // against, so it's enough to prove readers and the writer never overlap.
+        let state = Arc::new(AtomicIsize::new(0));
+
+        let mut handles = Vec::new();
+
+        for _ in 0..4 {
+            let store = store.clone();
+            let path = path.clone();
+            let state = state.clone();
+
+            handles.push(tokio::spawn(async move {
+                let _guard = store.read_lock(&path).await;
+
+                let seen = state.fetch_add(1, Ordering::SeqCst) + 1;
+                assert!(seen > 0, "reader overlapped with a writer");
+
+                tokio::task::yield_now().await;
+
+                state.fetch_sub(1, Ordering::SeqCst);
+            }));
+        }
+
+        {
+            let store = store.clone();
+            let path = path.clone();
+            let state = state.clone();
+
+            handles.push(tokio::spawn(async move {
+                let _guard = store.write_lock(&path).await;
+
+                let seen = state.fetch_sub(1, Ordering::SeqCst) - 1;
+                assert_eq!(seen, -1, "writer overlapped with a reader or another writer");
+
+                tokio::task::yield_now().await;
+
+                state.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn filesystem_store_update_preserves_monotonicity_under_concurrent_writers() {
+        let store = Arc::new(FilesystemStore::new(None));
+        let path = unique_temp_path("fs-update-race");
+        let markers: Vec<_> = (1..=8).map(marker).collect();
+
+        let handles = markers.clone().into_iter().map(|read_marker| {
+            let store = store.clone();
+            let path = path.clone();
+
+            // Mirrors `FilesystemStore::update`'s body directly against a throwaway
+            // path, since going through the public API would resolve the path under
+            // the real data dir via `dir_path`.
+            tokio::spawn(async move {
+                let _guard = store.write_lock(&path).await;
+                let metadata = store.read_from_disk(&path).await.unwrap().unwrap_or_default();
+
+                if let Some(metadata) = merged_read_marker(metadata, read_marker) {
+                    store.write_atomic(&path, &metadata).await.unwrap();
+                }
+            })
+        });
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let saved = store.read_from_disk(&path).await.unwrap().unwrap();
+        assert_eq!(saved.read_marker, markers.into_iter().max());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_update_preserves_monotonicity_under_concurrent_writers() {
+        // `server::Server` isn't otherwise constructed in this module; assumed to
+        // parse from a plain name the same way `ReadMarker` parses from an RFC3339
+        // string above.
+        let server: server::Server = "test.server".parse().unwrap();
+        let kind = Kind::Logs;
+
+        let store = Arc::new(InMemoryStore::new());
+        let markers: Vec<_> = (1..=8).map(marker).collect();
+
+        let handles = markers.clone().into_iter().map(|read_marker| {
+            let store = store.clone();
+            let server = server.clone();
+            let kind = kind.clone();
+
+            tokio::spawn(async move { store.update(&server, &kind, &read_marker).await.unwrap() })
+        });
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let saved = store.get(&server, &kind).await.unwrap().unwrap();
+        assert_eq!(saved.read_marker, markers.into_iter().max());
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_treats_expired_entries_as_absent() {
+        let server: server::Server = "test.server".parse().unwrap();
+        let kind = Kind::Logs;
+
+        let store = InMemoryStore::with_ttl(chrono::Duration::milliseconds(10));
+
+        store
+            .put(
+                &server,
+                &kind,
+                Metadata {
+                    read_marker: Some(marker(1)),
+                    last_triggers_unread: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(store.get(&server, &kind).await.unwrap().is_some());
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        assert!(store.get(&server, &kind).await.unwrap().is_none());
+
+        // `get` should have evicted the expired entry rather than just masking it.
+        let key = (server, kind);
+        assert!(!store.entries.read().await.contains_key(&key));
+    }
 }